@@ -0,0 +1,115 @@
+//! Git repository secret scanner in Rust
+//!
+//! # Usage
+//! ```text
+//! choctaw_hog [FLAGS] [OPTIONS] <GITPATH>
+//!
+//!FLAGS:
+//!        --caseinsensitive    Sets the case insensitive flag for all regexes
+//!        --entropy            Enables entropy scanning
+//!        --prettyprint        Output the JSON in human readable format
+//!        --interactive        Prompt on the TTY for SSH passphrases and HTTPS tokens
+//!    -v, --verbose            Sets the level of debugging information
+//!    -h, --help               Prints help information
+//!    -V, --version            Prints version information
+//!
+//!OPTIONS:
+//!    -o, --outputfile <OUTPUT>      Sets the path to write the scanner results to (stdout by default)
+//!        --regex <REGEX>            Sets a custom regex JSON file
+//!        --sincecommit <COMMIT>     Only scans commits more recent than this commit hash
+//!        --sshkeypath <SSHKEYPATH>  Path to a private SSH key for cloning, tried after the SSH agent
+//!        --sshkeyphrase <PHRASE>    Passphrase for the SSH key, if required
+//!        --httpsusername <USER>     Username for HTTPS cloning of private repos
+//!        --httpstoken <TOKEN>       Password/token for HTTPS cloning of private repos
+//!        --git-backend <BACKEND>    Clone backend: "lib" (default) or "cli" (shells out to system git)
+//!        --threads <THREADS>        Number of threads to scan commits with (default 1)
+//!
+//!ARGS:
+//!    <GITPATH>    The path (local, https://, ssh://, or scp-style) to the Git repository to scan
+//! ```
+
+#[macro_use]
+extern crate clap;
+
+use clap::ArgMatches;
+use log::{self, info};
+use simple_error::SimpleError;
+use tempdir::TempDir;
+
+use rusty_hogs::git_scanning::{CloneOptions, GitScanner};
+use rusty_hogs::{SecretScanner, SecretScannerBuilder};
+
+fn main() {
+    let matches = clap_app!(choctaw_hog =>
+        (version: "0.4.5")
+        (author: "Scott Cutler <scutler@newrelic.com>")
+        (about: "Git secret scanner in Rust.")
+        (@arg REGEX: --regex +takes_value "Sets a custom regex JSON file")
+        (@arg GITPATH: +required "The path (local, https://, ssh://, or scp-style) to the Git repository to scan")
+        (@arg VERBOSE: -v --verbose ... "Sets the level of debugging information")
+        (@arg ENTROPY: --entropy ... "Enables entropy scanning")
+        (@arg CASE: --caseinsensitive "Sets the case insensitive flag for all regexes")
+        (@arg OUTPUT: -o --outputfile +takes_value "Sets the path to write the scanner results to (stdout by default)")
+        (@arg PRETTYPRINT: --prettyprint "Output the JSON in human readable format")
+        (@arg SINCECOMMIT: --sincecommit +takes_value "Only scans commits more recent than this commit hash")
+        (@arg SSHKEYPATH: --sshkeypath +takes_value "Path to a private SSH key for cloning, tried after the SSH agent")
+        (@arg SSHKEYPHRASE: --sshkeyphrase +takes_value "Passphrase for the SSH key, if required")
+        (@arg HTTPSUSERNAME: --httpsusername +takes_value "Username for HTTPS cloning of private repos")
+        (@arg HTTPSTOKEN: --httpstoken +takes_value "Password/token for HTTPS cloning of private repos")
+        (@arg INTERACTIVE: --interactive "Prompt on the TTY for SSH passphrases and HTTPS tokens")
+        (@arg GITBACKEND: --("git-backend") +takes_value "Clone backend: \"lib\" (default) or \"cli\" (shells out to system git)")
+        (@arg THREADS: --threads +takes_value "Number of threads to scan commits with (default 1)")
+    )
+        .get_matches();
+    match run(&matches) {
+        Ok(()) => {}
+        Err(e) => panic!("error: {}", e),
+    }
+}
+
+fn run(arg_matches: &ArgMatches) -> Result<(), SimpleError> {
+    // Set logging
+    SecretScanner::set_logging(arg_matches.occurrences_of("VERBOSE"));
+
+    // Initialize some variables
+    let gitpath = arg_matches.value_of("GITPATH").unwrap();
+    let sshkeypath = arg_matches.value_of("SSHKEYPATH");
+    let sshkeyphrase = arg_matches.value_of("SSHKEYPHRASE");
+    let httpsusername = arg_matches.value_of("HTTPSUSERNAME");
+    let httpstoken = arg_matches.value_of("HTTPSTOKEN");
+    let since_commit = arg_matches.value_of("SINCECOMMIT");
+    let scan_entropy = arg_matches.is_present("ENTROPY");
+    let interactive = arg_matches.is_present("INTERACTIVE");
+    let git_backend = match arg_matches.value_of("GITBACKEND") {
+        Some("cli") => rusty_hogs::git_scanning::GitBackend::Cli,
+        Some("lib") | None => rusty_hogs::git_scanning::GitBackend::Lib,
+        Some(other) => panic!("unknown --git-backend value: {}", other),
+    };
+    let threads: usize = arg_matches
+        .value_of("THREADS")
+        .map(|t| match t.parse::<usize>() {
+            Ok(n) if n > 0 => n,
+            _ => panic!("--threads must be a positive integer"),
+        })
+        .unwrap_or(1);
+    let secret_scanner = SecretScannerBuilder::new().conf_argm(arg_matches).build();
+    let mut git_scanner = GitScanner::new(secret_scanner);
+
+    let dest_dir = TempDir::new("rusty_hogs").unwrap();
+    let clone_opts = CloneOptions {
+        sshkeypath,
+        sshkeyphrase,
+        httpsusername,
+        httpstoken,
+        interactive,
+        backend: git_backend,
+    };
+    git_scanner = git_scanner.init_git_repo(gitpath, dest_dir.path(), clone_opts);
+
+    // Do the scan
+    let findings = git_scanner.perform_scan(None, since_commit, scan_entropy, threads);
+    info!("Found {} secrets", findings.len());
+    git_scanner.secret_scanner.output_findings(&findings);
+
+    Ok(())
+}
@@ -1,20 +1,21 @@
 use crate::SecretScanner;
 use encoding::all::ASCII;
 use encoding::{DecoderTrap, Encoding};
-use git2::{DiffFormat, Revwalk, Commit};
+use git2::{DiffFormat, Revwalk};
 use git2::{DiffOptions, Repository, Time};
+use git_url_parse::{GitUrl, Scheme as GitUrlScheme};
 use log::{self, info};
 use regex::bytes::Matches;
 use serde::{Deserialize, Serialize};
 use simple_error::SimpleError;
 use simple_logger;
 use simple_logger::init_with_level;
+use rayon::prelude::*;
 use std::collections::{BTreeMap, HashSet};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::str;
 use tempdir::TempDir;
-use url::{ParseError, Url};
 use chrono::NaiveDateTime;
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash)]
@@ -39,10 +40,40 @@ pub enum GitScheme {
     Git
 }
 
+/// Selects how `init_git_repo` acquires a copy of the remote repository to scan.
+#[derive(Clone, Copy)]
+pub enum GitBackend {
+    /// Clone with libgit2 (via `git2-rs`), authenticating with the credential chain built by
+    /// `with_authentication`. This is the default, and works for agent/key-file/token setups.
+    Lib,
+    /// Shell out to the system `git` binary, inheriting the user's `~/.ssh/config`,
+    /// `~/.gitconfig`, and credential helpers. libgit2 can't drive SSO-backed credential helpers,
+    /// 2FA prompts, or custom SSH configs, so this backend exists as a fallback for the auth setups
+    /// libgit2 simply can't handle.
+    Cli,
+}
+
+/// Credential and behavior inputs for acquiring a copy of a repository, grouped into one struct
+/// so adjacent `Option<&str>` fields of the same type (e.g. `httpsusername`/`httpstoken`,
+/// `sshkeypath`/`sshkeyphrase`) can't be silently transposed at a call site the way they could
+/// when threaded through `init_git_repo`/`with_authentication` as individual positional params.
+#[derive(Clone, Copy)]
+pub struct CloneOptions<'a> {
+    pub sshkeypath: Option<&'a str>,
+    pub sshkeyphrase: Option<&'a str>,
+    pub httpsusername: Option<&'a str>,
+    pub httpstoken: Option<&'a str>,
+    pub interactive: bool,
+    pub backend: GitBackend,
+}
+
 /// Contains helper functions for performing scans of Git repositories
 pub struct GitScanner {
     pub secret_scanner: SecretScanner,
-    pub repo: Option<Repository>
+    pub repo: Option<Repository>,
+    /// On-disk location of `repo`. `perform_scan` reopens this path from each worker thread since
+    /// `git2::Repository` isn't `Sync`.
+    pub repo_path: Option<PathBuf>,
 }
 
 /// Acts as a wrapper around a SecretScanner object to provide helper functions for performing
@@ -53,11 +84,16 @@ impl GitScanner {
     /// it to this constructor method.
     pub fn new(secret_scanner: SecretScanner) -> GitScanner {
         GitScanner { secret_scanner,
-                     repo: None }
+                     repo: None,
+                     repo_path: None }
     }
 
-    pub fn perform_scan(&mut self, glob: Option<&str>, since_commit: Option<&str>, scan_entropy: bool) -> HashSet<GitFinding> {
+    pub fn perform_scan(&mut self, glob: Option<&str>, since_commit: Option<&str>, scan_entropy: bool, threads: usize) -> HashSet<GitFinding> {
         let repo = self.repo.as_ref().unwrap();
+        let repo_path = self
+            .repo_path
+            .clone()
+            .expect("repo_path must be set (via init_git_repo) before scanning");
         let mut revwalk = repo.revwalk().unwrap();
         revwalk.push_glob("*").unwrap(); //easy mode: iterate over all the commits
 
@@ -73,134 +109,369 @@ impl GitScanner {
             Time::new(0, 0)
         };
 
-        // convert our iterator of OIDs to commit objects
-        let revwalk = revwalk.map(|id| repo.find_commit(id.unwrap())).filter(|c| c.as_ref().unwrap().time() >= since_time_obj);
+        // convert our iterator of OIDs to commit objects, and filter down to the non-merge
+        // commits we actually want to diff, up front and on the main thread - this is the
+        // sequential part, the diffing below is what we fan out across the worker pool
+        let commit_ids: Vec<git2::Oid> = revwalk
+            .map(|id| repo.find_commit(id.unwrap()))
+            .filter(|c| c.as_ref().unwrap().time() >= since_time_obj)
+            .filter_map(|c| {
+                let commit = c.unwrap();
+                if commit.parents().len() > 1 {
+                    None
+                } else {
+                    Some(commit.id())
+                }
+            })
+            .collect();
 
-        let mut findings: HashSet<GitFinding> = HashSet::new();
-        // The main loop - scan each line of each diff of each commit for regex matches
-        for commit in revwalk {
-            // based on https://github.com/alexcrichton/git2-rs/blob/master/examples/log.rs
-            let commit: Commit = commit.unwrap();
-            info!("Scanning commit {}", commit.id());
-            if commit.parents().len() > 1 {
-                continue;
-            }
-            let a = if commit.parents().len() == 1 {
-                let parent = commit.parent(0).unwrap();
-                Some(parent.tree().unwrap())
-            } else {
-                None
-            };
-            let b = commit.tree().unwrap();
-            let mut diffopts = DiffOptions::new();
-            diffopts.force_binary(true);
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()
+            .unwrap();
+        let secret_scanner = &self.secret_scanner;
+
+        // The main loop - scan each line of each diff of each commit for regex matches. Each
+        // rayon job opens its own Repository handle on the already-cloned repo_path via map_init
+        // (since git2::Repository isn't Sync and can't be shared across threads), then reuses
+        // that handle for the commits in that job; a thread may open more than one handle.
+        let findings: HashSet<GitFinding> = pool.install(|| {
+            commit_ids
+                .par_iter()
+                .map_init(
+                    || Repository::open(&repo_path).unwrap(),
+                    |repo, commit_id| {
+                        let commit = repo.find_commit(*commit_id).unwrap();
+                        info!("Scanning commit {}", commit.id());
+                        let a = if commit.parents().len() == 1 {
+                            let parent = commit.parent(0).unwrap();
+                            Some(parent.tree().unwrap())
+                        } else {
+                            None
+                        };
+                        let b = commit.tree().unwrap();
+                        let mut diffopts = DiffOptions::new();
+                        diffopts.force_binary(true);
 
-            let diff = repo
-                .diff_tree_to_tree(a.as_ref(), Some(&b), Some(&mut diffopts))
-                .unwrap();
+                        let diff = repo
+                            .diff_tree_to_tree(a.as_ref(), Some(&b), Some(&mut diffopts))
+                            .unwrap();
 
-            // secondary loop that occurs for each *line* in the diff
-            diff.print(DiffFormat::Patch, |delta, _hunk, line| {
-                let new_line = line.content();
-                let matches_map: BTreeMap<&String, Matches> = self.secret_scanner.get_matches(new_line);
+                        let mut commit_findings: Vec<GitFinding> = Vec::new();
+                        // secondary loop that occurs for each *line* in the diff
+                        diff.print(DiffFormat::Patch, |delta, _hunk, line| {
+                            let new_line = line.content();
+                            let matches_map: BTreeMap<&String, Matches> =
+                                secret_scanner.get_matches(new_line);
 
-                for (reason, match_iterator) in matches_map {
-                    let mut secrets: Vec<String> = Vec::new();
-                    for matchobj in match_iterator {
-                        secrets.push(
-                            ASCII
-                                .decode(
-                                    &new_line[matchobj.start()..matchobj.end()],
-                                    DecoderTrap::Ignore,
-                                )
-                                .unwrap_or_else(|_| "<STRING DECODE ERROR>".parse().unwrap()),
-                        );
+                            for (reason, match_iterator) in matches_map {
+                                let mut secrets: Vec<String> = Vec::new();
+                                for matchobj in match_iterator {
+                                    secrets.push(
+                                        ASCII
+                                            .decode(
+                                                &new_line[matchobj.start()..matchobj.end()],
+                                                DecoderTrap::Ignore,
+                                            )
+                                            .unwrap_or_else(|_| "<STRING DECODE ERROR>".parse().unwrap()),
+                                    );
+                                }
+                                if !secrets.is_empty() {
+                                    commit_findings.push(GitFinding {
+                                        commit_hash: commit.id().to_string(),
+                                        commit: commit.message().unwrap().to_string(),
+                                        diff: ASCII
+                                            .decode(&new_line, DecoderTrap::Ignore)
+                                            .unwrap_or_else(|_| "<STRING DECODE ERROR>".parse().unwrap()),
+                                        date: NaiveDateTime::from_timestamp(commit.time().seconds(), 0).to_string(),
+                                        strings_found: secrets.clone(),
+                                        path: delta
+                                            .new_file()
+                                            .path()
+                                            .unwrap()
+                                            .to_str()
+                                            .unwrap()
+                                            .to_string(),
+                                        reason: reason.clone(),
+                                    });
+                                }
+                            }
+
+                            if scan_entropy {
+                                let ef = SecretScanner::get_entropy_findings(new_line);
+                                if !ef.is_empty() {
+                                    commit_findings.push(GitFinding {
+                                        commit: commit.message().unwrap().to_string(),
+                                        commit_hash: commit.id().to_string(),
+                                        diff: ASCII
+                                            .decode(&new_line, DecoderTrap::Ignore)
+                                            .unwrap_or_else(|_| "<STRING DECODE ERROR>".parse().unwrap()),
+                                        date: NaiveDateTime::from_timestamp(commit.time().seconds(), 0).to_string(),
+                                        strings_found: ef,
+                                        path: delta
+                                            .new_file()
+                                            .path()
+                                            .unwrap()
+                                            .to_str()
+                                            .unwrap()
+                                            .to_string(),
+                                        reason: "Entropy".to_string(),
+                                    });
+                                }
+                            }
+                            true
+                        })
+                            .unwrap();
+                        commit_findings
+                    },
+                )
+                .flatten()
+                .collect()
+        });
+        findings
+    }
+
+    /// Drives a clone (or other authenticated operation) through libgit2's repeated credential
+    /// callback invocations, trying each allowed [`git2::CredentialType`] in priority order - SSH
+    /// agent, then an explicit key file, then a configured HTTPS token, then a bare username, then
+    /// `git2::Cred::default()` (git credential helpers are not consulted) - and remembering which methods
+    /// have already been tried so the callback can't be asked to loop forever. `f` receives the
+    /// populated `RemoteCallbacks` and performs the actual libgit2 operation; it may be invoked
+    /// more than once as methods are exhausted.
+    fn with_authentication<T>(
+        url: &str,
+        username: &str,
+        opts: &CloneOptions,
+        mut f: impl FnMut(git2::RemoteCallbacks) -> Result<T, git2::Error>,
+    ) -> Result<T, SimpleError> {
+        let sshkeypath = opts.sshkeypath;
+        let sshkeyphrase = opts.sshkeyphrase;
+        let httpsusername = opts.httpsusername;
+        let httpstoken = opts.httpstoken;
+        let interactive = opts.interactive;
+        let mut tried_agent = false;
+        let mut tried_sshkey = false;
+        let mut tried_userpass = false;
+        let mut tried_username = false;
+        let mut tried_default = false;
+        let mut any_attempted;
+        // lazily filled via askpass prompting when --interactive is set and no passphrase/token
+        // was supplied on the command line
+        let mut prompted_sshkeyphrase: Option<String> = None;
+        let mut prompted_httpstoken: Option<String> = None;
+
+        loop {
+            any_attempted = false;
+            // reset per attempt: a retry hands libgit2 a fresh transfer, so received_objects()
+            // restarts at 0 and a counter left over from an earlier failed attempt would underflow
+            let mut last_reported_objects = 0;
+            let mut cb = git2::RemoteCallbacks::new();
+            cb.transfer_progress(|progress| {
+                let received = progress.received_objects();
+                let total = progress.total_objects();
+                // throttle to one log line per 100 objects, plus one when the last object arrives,
+                // so big clones don't flood the log (this callback keeps firing with
+                // received == total while deltas are resolved)
+                if (received == total && received != last_reported_objects)
+                    || received - last_reported_objects >= 100
+                {
+                    last_reported_objects = received;
+                    info!(
+                        "Cloning {}: received {}/{} objects ({} bytes)",
+                        url,
+                        received,
+                        total,
+                        progress.received_bytes()
+                    );
+                }
+                true
+            });
+            cb.credentials(|_url, username_from_url, allowed| {
+                let username = username_from_url.unwrap_or(username);
+                if allowed.contains(git2::CredentialType::SSH_KEY) {
+                    if !tried_agent {
+                        tried_agent = true;
+                        any_attempted = true;
+                        info!("Attempting to read SSH credentials from ssh-agent...");
+                        if let Ok(cred) = git2::Cred::ssh_key_from_agent(username) {
+                            return Ok(cred);
+                        }
                     }
-                    if !secrets.is_empty() {
-                        findings.insert(GitFinding {
-                            commit_hash: commit.id().to_string(),
-                            commit: commit.message().unwrap().to_string(),
-                            diff: ASCII
-                                .decode(&new_line, DecoderTrap::Ignore)
-                                .unwrap_or_else(|_| "<STRING DECODE ERROR>".parse().unwrap()),
-                            date: NaiveDateTime::from_timestamp(commit.time().seconds(), 0).to_string(),
-                            strings_found: secrets.clone(),
-                            path: delta
-                                .new_file()
-                                .path()
-                                .unwrap()
-                                .to_str()
-                                .unwrap()
-                                .to_string(),
-                            reason: reason.clone(),
-                        });
+                    if !tried_sshkey {
+                        if let Some(keypath) = sshkeypath {
+                            tried_sshkey = true;
+                            any_attempted = true;
+                            let passphrase = sshkeyphrase.map(|p| p.to_string()).or_else(|| {
+                                if interactive {
+                                    prompted_sshkeyphrase = GitScanner::prompt_secret(&format!(
+                                        "Passphrase for SSH key {:?}: ",
+                                        keypath
+                                    ));
+                                    prompted_sshkeyphrase.clone()
+                                } else {
+                                    None
+                                }
+                            });
+                            info!("Attempting to read SSH credentials from {:?}...", keypath);
+                            if let Ok(cred) = git2::Cred::ssh_key(
+                                username,
+                                None,
+                                Path::new(keypath),
+                                passphrase.as_deref(),
+                            ) {
+                                return Ok(cred);
+                            }
+                        }
                     }
                 }
+                if allowed.contains(git2::CredentialType::USER_PASS_PLAINTEXT) && !tried_userpass {
+                    let token = httpstoken.map(|t| t.to_string()).or_else(|| {
+                        if interactive {
+                            prompted_httpstoken =
+                                GitScanner::prompt_secret("HTTPS password/token: ");
+                            prompted_httpstoken.clone()
+                        } else {
+                            None
+                        }
+                    });
+                    if let Some(token) = token {
+                        tried_userpass = true;
+                        any_attempted = true;
+                        info!("Attempting to authenticate with the configured HTTPS token...");
+                        return git2::Cred::userpass_plaintext(httpsusername.unwrap_or(""), &token);
+                    }
+                }
+                if allowed.contains(git2::CredentialType::USERNAME) && !tried_username {
+                    tried_username = true;
+                    any_attempted = true;
+                    return git2::Cred::username(username);
+                }
+                if allowed.contains(git2::CredentialType::DEFAULT) && !tried_default {
+                    tried_default = true;
+                    any_attempted = true;
+                    return git2::Cred::default();
+                }
+                Err(git2::Error::from_str("no more authentication methods to try"))
+            });
 
-                if scan_entropy {
-                    let ef = SecretScanner::get_entropy_findings(new_line);
-                    if !ef.is_empty() {
-                        findings.insert(GitFinding {
-                            commit: commit.message().unwrap().to_string(),
-                            commit_hash: commit.id().to_string(),
-                            diff: ASCII
-                                .decode(&new_line, DecoderTrap::Ignore)
-                                .unwrap_or_else(|_| "<STRING DECODE ERROR>".parse().unwrap()),
-                            date: NaiveDateTime::from_timestamp(commit.time().seconds(), 0).to_string(),
-                            strings_found: ef,
-                            path: delta
-                                .new_file()
-                                .path()
-                                .unwrap()
-                                .to_str()
-                                .unwrap()
-                                .to_string(),
-                            reason: "Entropy".to_string(),
-                        });
+            match f(cb) {
+                Ok(v) => return Ok(v),
+                Err(e) => {
+                    if !any_attempted {
+                        return Err(SimpleError::new(format!(
+                            "Authentication failed for {:?}: {:?}",
+                            url, e
+                        )));
                     }
                 }
-                true
-            })
-                .unwrap();
+            }
+        }
+    }
+
+    /// Clones `git_url` into `dest_dir` by shelling out to the system `git` binary instead of
+    /// libgit2. The child process inherits the invoking user's environment (`~/.ssh/config`,
+    /// `~/.gitconfig`, credential helpers, SSO plugins) so auth setups libgit2 can't drive still
+    /// work. `git`'s stdin/stdout/stderr are inherited, not captured: its output goes straight to
+    /// the terminal, and on failure this panics with `git`'s exit status only. `perform_scan` is
+    /// unaffected since it only ever sees the resulting `Repository::open` handle.
+    fn clone_with_cli(git_url: &str, dest_dir: &Path) -> Repository {
+        // Command::output() closes the child's stdin, so a `git` that needs to prompt (SSH
+        // passphrase, 2FA, SSO credential helper, host-key confirmation) would just hang against
+        // an already-EOF'd stdin with its prompts buffered and invisible until exit. Inherit all
+        // three standard streams instead so `git` can talk to the real terminal.
+        let status = std::process::Command::new("git")
+            .arg("clone")
+            .arg(git_url)
+            .arg(dest_dir)
+            .stdin(std::process::Stdio::inherit())
+            .stdout(std::process::Stdio::inherit())
+            .stderr(std::process::Stdio::inherit())
+            .status()
+            .unwrap_or_else(|e| panic!("Failed to execute the system `git` binary: {:?}", e));
+        if !status.success() {
+            panic!(
+                "<GITPATH> {:?} couldn't be cloned by the system `git` binary (exit status {:?})",
+                git_url, status
+            );
+        }
+        match Repository::open(dest_dir) {
+            Ok(r) => r,
+            Err(e) => panic!(
+                "`git` cloned {:?} successfully but the checkout at {:?} couldn't be opened: {:?}",
+                git_url, dest_dir, e
+            ),
+        }
+    }
+
+    /// Prompts for a secret (SSH key passphrase or HTTPS password/token) on the controlling TTY,
+    /// without echoing input. If `SSH_ASKPASS` is set, that helper command is invoked instead and
+    /// its stdout used as the secret, matching the `ssh`/`git` askpass convention. Returns `None`
+    /// if neither a TTY nor an askpass helper is available.
+    fn prompt_secret(prompt: &str) -> Option<String> {
+        if let Ok(askpass) = std::env::var("SSH_ASKPASS") {
+            return std::process::Command::new(askpass)
+                .arg(prompt)
+                .output()
+                .ok()
+                .and_then(|output| String::from_utf8(output.stdout).ok())
+                .map(|s| s.trim_end_matches(&['\r', '\n'][..]).to_string());
+        }
+        rpassword::prompt_password(prompt).ok()
+    }
+
+    /// Clones an `http(s)://` git remote, authenticating with a username/token pair when one is
+    /// supplied. The token is passed as the password half of a plaintext userpass credential;
+    /// GitHub/GitLab/Bitbucket personal access tokens work equally well as the username half.
+    fn get_https_git_repo(https_git_url: &str, dest_dir: &Path, opts: &CloneOptions) -> Repository {
+        let result = GitScanner::with_authentication(
+            https_git_url,
+            opts.httpsusername.unwrap_or(""),
+            opts,
+            |cb| {
+                let mut fo = git2::FetchOptions::new();
+                fo.remote_callbacks(cb);
+                let mut builder = git2::build::RepoBuilder::new();
+                builder.fetch_options(fo);
+                builder.clone(https_git_url, dest_dir)
+            },
+        );
+        match result {
+            Ok(r) => r,
+            Err(e) => panic!(
+                "<GITPATH> {:?} is an HTTP(s) GIT URL but couldn't be cloned:\n{:?}",
+                https_git_url, e
+            ),
         }
-        findings
     }
 
     fn get_ssh_git_repo(
         ssh_git_url: &str,
         dest_dir: &Path,
-        sshkeypath: Option<&str>,
-        sshkeyphrase: Option<&str>,
+        opts: &CloneOptions,
         username: &str,
     ) -> Repository {
         info!("username in get_ssh_git_repo: {:?}", username);
-        let mut cb = git2::RemoteCallbacks::new();
-        if sshkeypath.is_some() {
-            cb.credentials(|_, _, _| {
-                info!("SSHKEYPATH detected, attempting to read credentials from supplied path...");
-                let credentials = git2::Cred::ssh_key(
-                    username,
-                    None,
-                    Path::new(sshkeypath.unwrap()),
-                    sshkeyphrase,
-                )
-                    .expect("Cannot create credentials object.");
-                Ok(credentials)
-            });
-        } else {
-            cb.credentials(|_, _, _| {
-                info!("no SSHKEYPATH detected, attempting to read credentials from ssh_agent...");
-                let credentials = git2::Cred::ssh_key_from_agent(username)
-                    .expect("Cannot create credentials object from ssh_agent");
-                Ok(credentials)
-            });
-        }
-        let mut fo = git2::FetchOptions::new();
-        fo.remote_callbacks(cb);
-        let mut builder = git2::build::RepoBuilder::new();
-        builder.fetch_options(fo);
-        info!("SSH Git credentials successfully initialized, attempting to clone the repo...");
-        match builder.clone(ssh_git_url, dest_dir) {
+        // httpsusername/httpstoken are irrelevant to an SSH clone; clear them so they can't be
+        // offered as USER_PASS_PLAINTEXT credentials for a remote that never asked for HTTPS auth.
+        let ssh_opts = CloneOptions {
+            httpsusername: None,
+            httpstoken: None,
+            ..*opts
+        };
+        let result = GitScanner::with_authentication(
+            ssh_git_url,
+            username,
+            &ssh_opts,
+            |cb| {
+                let mut fo = git2::FetchOptions::new();
+                fo.remote_callbacks(cb);
+                let mut builder = git2::build::RepoBuilder::new();
+                builder.fetch_options(fo);
+                info!("SSH Git credentials successfully initialized, attempting to clone the repo...");
+                builder.clone(ssh_git_url, dest_dir)
+            },
+        );
+        match result {
             Ok(r) => r,
             Err(e) => panic!(
                 "<GITPATH> {:?} is a SSH GIT URL but couldn't be cloned:\n{:?}",
@@ -210,49 +481,58 @@ impl GitScanner {
     }
 
     /// Initialize a [Repository](https://docs.rs/git2/0.10.2/git2/struct.Repository.html) object
-    pub fn init_git_repo(mut self, path: &str, dest_dir: &Path, sshkeypath: Option<&str>,
-                    sshkeyphrase: Option<&str>) -> GitScanner {
-        let url = Url::parse(path);
-        // try to figure out the format of the path
-        let scheme: GitScheme = match &url {
-            Ok(url) => match url.scheme().to_ascii_lowercase().as_ref() {
-                "http" => {
-                    info!("Git scheme detected as http://, performing a clone...");
-                    GitScheme::Http
-                }
-                "https" => {
-                    info!("Git scheme detected as https:// , performing a clone...");
-                    GitScheme::Http
-                }
-                "file" => {
-                    info!("Git scheme detected as file://, performing a clone...");
-                    GitScheme::Localpath
-                }
-                "ssh" => {
-                    info!("Git scheme detected as ssh://, performing a clone...");
-                    GitScheme::Ssh
-                }
-                "git" => {
-                    info!("Git scheme detected as git://, performing a clone...");
-                    GitScheme::Git
-                }
-                s => panic!(
-                    "Error parsing GITPATH {:?}, please include the username with \"git@\"",
-                    s
-                ),
-            },
-            Err(e) => match e {
-                ParseError::RelativeUrlWithoutBase => {
-                    info!(
-                        "Git scheme detected as a relative path, attempting to open on the local \
-                     file system and then falling back to SSH..."
-                    );
-                    GitScheme::Relativepath
-                }
-                e => panic!("Unknown error parsing GITPATH: {:?}", e),
-            },
+    pub fn init_git_repo(mut self, path: &str, dest_dir: &Path, opts: CloneOptions) -> GitScanner {
+        if let GitBackend::Cli = opts.backend {
+            info!("--git-backend=cli selected, shelling out to the system git binary...");
+            self.repo = Some(GitScanner::clone_with_cli(path, dest_dir));
+            self.repo_path = Some(dest_dir.to_path_buf());
+            return self;
+        }
+
+        // GITPATH strings with no "scheme://" prefix - plain local paths, relative paths, and the
+        // scp-like `user@host:path` SSH syntax - do parse successfully with git-url-parse, but to
+        // a concrete scheme (File or Ssh) that's wrong for routing: it would send a checked-out
+        // local path through Repository::clone instead of Repository::open, and an scp-style
+        // remote straight to SSH with no chance to try opening it as a local path first. So these
+        // are detected up front and routed to GitScheme::Relativepath before ever calling
+        // GitUrl::parse, matching baseline's behavior of treating any scheme-less GITPATH as a path
+        // to try opening locally before falling back to SSH.
+        let scheme: GitScheme = if !path.contains("://") {
+            info!(
+                "Git scheme detected as a relative path, attempting to open on the local \
+                 file system and then falling back to SSH..."
+            );
+            GitScheme::Relativepath
+        } else {
+            match GitUrl::parse(path) {
+                Ok(git_url) => match git_url.scheme {
+                    GitUrlScheme::Https | GitUrlScheme::Http => {
+                        info!("Git scheme detected as http(s)://, performing a clone...");
+                        GitScheme::Http
+                    }
+                    GitUrlScheme::File => {
+                        info!("Git scheme detected as file://, performing a clone...");
+                        GitScheme::Localpath
+                    }
+                    GitUrlScheme::Ssh | GitUrlScheme::GitSsh => {
+                        info!("Git scheme detected as ssh://, performing a clone...");
+                        GitScheme::Ssh
+                    }
+                    GitUrlScheme::Git => {
+                        info!("Git scheme detected as git://, performing a clone...");
+                        GitScheme::Git
+                    }
+                    s => panic!(
+                        "Error parsing GITPATH {:?}, please include the username with \"git@\": {:?}",
+                        path, s
+                    ),
+                },
+                Err(e) => panic!("Unable to parse GITPATH {:?}: {:?}", path, e),
+            }
         };
+        let parsed = GitUrl::parse(path);
 
+        let mut repo_path = dest_dir.to_path_buf();
         self.repo = match scheme {
             GitScheme::Localpath => match Repository::clone(path, dest_dir) {
                 Ok(r) => Some(r),
@@ -261,40 +541,33 @@ impl GitScanner {
                     path, e
                 ),
             },
-            GitScheme::Http => match Repository::clone(path, dest_dir) {
-                Ok(r) => Some(r),
-                Err(e) => panic!(
-                    "<GITPATH> {:?} is an HTTP(s) URL but couldn't be opened: {:?}",
-                    path, e
-                ),
-            },
-            GitScheme::Git => {
-                let url = url.unwrap(); // we already have assurance this passed successfully
-                let username = match url.username() {
-                    "" => "git",
-                    s => s
+            GitScheme::Http => Some(GitScanner::get_https_git_repo(path, dest_dir, &opts)),
+            GitScheme::Git | GitScheme::Ssh => {
+                let git_url = parsed.unwrap(); // we already have assurance this passed successfully
+                let username = match git_url.user {
+                    Some(ref u) if !u.is_empty() => u.as_str(),
+                    _ => "git",
                 };
-                Some(GitScanner::get_ssh_git_repo(path, dest_dir, sshkeypath, sshkeyphrase, username))
-            }
-            GitScheme::Ssh => {
-                let url = url.unwrap(); // we already have assurance this passed successfully
-                let username = url.username();
-                Some(GitScanner::get_ssh_git_repo(path, dest_dir, sshkeypath, sshkeyphrase, username))
+                Some(GitScanner::get_ssh_git_repo(path, dest_dir, &opts, username))
             }
             // since @ and : are valid characters in linux paths, we need to try both opening locally
             // and over SSH. This SSH syntax is normal for Github.
             GitScheme::Relativepath => match Repository::open(path) {
                 //
-                Ok(r) => Some(r),
+                Ok(r) => {
+                    repo_path = PathBuf::from(path);
+                    Some(r)
+                }
                 Err(_) => {
-                    let username = match path.find('@') {
-                        Some(i) => path.split_at(i).0,
-                        None => "git",
+                    let username = match parsed.ok().and_then(|git_url| git_url.user) {
+                        Some(u) if !u.is_empty() => u,
+                        _ => "git".to_string(),
                     };
-                    Some(GitScanner::get_ssh_git_repo(path, dest_dir, sshkeypath, sshkeyphrase, username))
+                    Some(GitScanner::get_ssh_git_repo(path, dest_dir, &opts, &username))
                 }
             },
         };
+        self.repo_path = Some(repo_path);
         self
     }
 }